@@ -0,0 +1,182 @@
+// Mass-balance and steady-state diagnostics: derived quantities the model
+// should conserve or converge to, tracked alongside the integration loop
+// rather than inferred after the fact from the raw pool traces.
+
+use crate::model::Model;
+use crate::AuxiliaryResults;
+
+/// Diagnostics computed for a single integration step.
+#[derive(Debug, Clone)]
+pub struct DiagnosticsRow {
+    /// `input - outflow - d(total)/dt`; should be approximately 0 for
+    /// correct flux bookkeeping.
+    pub balance: f64,
+    /// Whether any pool's concentration went negative (physically invalid).
+    pub negative_concentration: bool,
+    /// Whether steady state has been detected and sustained as of this step.
+    pub steady_state: bool,
+}
+
+/// Running steady-state detector: flags steady state once `||dydt||` has
+/// stayed below `tol` for `window` consecutive steps, and latches the
+/// pool amounts/fluxes at which it was first reached.
+pub struct Diagnostics {
+    tol: f64,
+    window: usize,
+    below_tol_run: usize,
+    reached: bool,
+    pub steady_state_pools: Option<Vec<f64>>,
+    pub steady_state_fluxes: Option<Vec<f64>>,
+}
+
+impl Diagnostics {
+    pub fn new(tol: f64, window: usize) -> Self {
+        Diagnostics {
+            tol,
+            window,
+            below_tol_run: 0,
+            reached: false,
+            steady_state_pools: None,
+            steady_state_fluxes: None,
+        }
+    }
+
+    /// Update the diagnostics for one completed integration step.
+    ///
+    /// `y`/`dydt` and `results` are the state/derivative/auxiliary values
+    /// at the end of the step; `flux_target` resolves each flux's target
+    /// pool (as built in `main`), used to pick out true outflows (target
+    /// `None`) from internal transfers, which cancel out of the total.
+    /// `total_prev`/`total_now` are the summed pool amounts before/after
+    /// the step, used to estimate `d(total)/dt` over `dt`.
+    pub fn step(
+        &mut self,
+        model: &Model,
+        t: f64,
+        y: &[f64],
+        dydt: &[f64],
+        results: &AuxiliaryResults,
+        flux_target: &[Option<usize>],
+        total_prev: f64,
+        total_now: f64,
+        dt: f64,
+    ) -> DiagnosticsRow {
+        let input: f64 = model.pools.iter().map(|p| p.input.value_at(t)).sum();
+        let outflow: f64 = results
+            .fluxes
+            .iter()
+            .zip(flux_target)
+            .filter(|(_, tgt)| tgt.is_none())
+            .map(|(flux, _)| *flux)
+            .sum();
+        let d_total_dt = (total_now - total_prev) / dt;
+        let balance = input - outflow - d_total_dt;
+
+        let negative_concentration = results.concentrations.iter().any(|&c| c < 0.0);
+
+        let norm_dydt = dydt.iter().map(|d| d * d).sum::<f64>().sqrt();
+        if norm_dydt < self.tol {
+            self.below_tol_run += 1;
+        } else {
+            self.below_tol_run = 0;
+        }
+        if !self.reached && self.below_tol_run >= self.window {
+            self.reached = true;
+            self.steady_state_pools = Some(y.to_vec());
+            self.steady_state_fluxes = Some(results.fluxes.clone());
+        }
+
+        DiagnosticsRow {
+            balance,
+            negative_concentration,
+            steady_state: self.reached,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Input, Pool};
+
+    fn one_pool_model(input: f64) -> Model {
+        Model {
+            pools: vec![Pool {
+                name: "A".into(),
+                volume: 1.0,
+                initial_amount: 5.0,
+                input: Input::Constant(input),
+            }],
+            fluxes: vec![],
+        }
+    }
+
+    #[test]
+    fn balance_is_zero_when_dtotal_matches_input_minus_outflow() {
+        let model = one_pool_model(3.0);
+        let results = AuxiliaryResults { concentrations: vec![1.0], fluxes: vec![1.0] };
+        let flux_target = vec![None]; // a true outflow, no target
+        let mut diag = Diagnostics::new(1e-4, 10);
+
+        // input 3.0, outflow 1.0 => d(total)/dt should be 2.0 for balance == 0.
+        let row = diag.step(&model, 0.0, &[7.0], &[2.0], &results, &flux_target, 5.0, 7.0, 1.0);
+        assert!(row.balance.abs() < 1e-12, "balance = {}", row.balance);
+    }
+
+    #[test]
+    fn balance_reflects_a_real_mismatch() {
+        let model = one_pool_model(3.0);
+        let results = AuxiliaryResults { concentrations: vec![1.0], fluxes: vec![1.0] };
+        let flux_target = vec![None];
+        let mut diag = Diagnostics::new(1e-4, 10);
+
+        // input 3.0, outflow 1.0, but total didn't move at all: balance == 2.0.
+        let row = diag.step(&model, 0.0, &[5.0], &[0.0], &results, &flux_target, 5.0, 5.0, 1.0);
+        assert!((row.balance - 2.0).abs() < 1e-12, "balance = {}", row.balance);
+    }
+
+    #[test]
+    fn negative_concentration_is_flagged() {
+        let model = one_pool_model(0.0);
+        let flux_target = vec![];
+        let mut diag = Diagnostics::new(1e-4, 10);
+
+        let ok = AuxiliaryResults { concentrations: vec![1.0, 2.0], fluxes: vec![] };
+        let row = diag.step(&model, 0.0, &[1.0, 2.0], &[0.0, 0.0], &ok, &flux_target, 3.0, 3.0, 1.0);
+        assert!(!row.negative_concentration);
+
+        let bad = AuxiliaryResults { concentrations: vec![-0.1, 2.0], fluxes: vec![] };
+        let row = diag.step(&model, 1.0, &[-0.1, 2.0], &[0.0, 0.0], &bad, &flux_target, 3.0, 1.9, 1.0);
+        assert!(row.negative_concentration);
+    }
+
+    #[test]
+    fn steady_state_flags_after_window_consecutive_low_dydt_steps() {
+        let model = one_pool_model(0.0);
+        let results = AuxiliaryResults { concentrations: vec![1.0], fluxes: vec![] };
+        let flux_target = vec![];
+        let mut diag = Diagnostics::new(0.1, 3);
+
+        for i in 0..2 {
+            let row = diag.step(&model, i as f64, &[5.0], &[0.01], &results, &flux_target, 5.0, 5.0, 1.0);
+            assert!(!row.steady_state, "should not be steady yet at step {}", i);
+        }
+        let row = diag.step(&model, 2.0, &[5.0], &[0.01], &results, &flux_target, 5.0, 5.0, 1.0);
+        assert!(row.steady_state);
+        assert_eq!(diag.steady_state_pools, Some(vec![5.0]));
+        assert_eq!(diag.steady_state_fluxes, Some(vec![]));
+    }
+
+    #[test]
+    fn a_high_dydt_step_resets_the_steady_state_run() {
+        let model = one_pool_model(0.0);
+        let results = AuxiliaryResults { concentrations: vec![1.0], fluxes: vec![] };
+        let flux_target = vec![];
+        let mut diag = Diagnostics::new(0.1, 2);
+
+        diag.step(&model, 0.0, &[5.0], &[0.01], &results, &flux_target, 5.0, 5.0, 1.0);
+        diag.step(&model, 1.0, &[5.0], &[5.0], &results, &flux_target, 5.0, 5.0, 1.0);
+        let row = diag.step(&model, 2.0, &[5.0], &[0.01], &results, &flux_target, 5.0, 5.0, 1.0);
+        assert!(!row.steady_state, "the high-dydt step should have reset the run");
+    }
+}
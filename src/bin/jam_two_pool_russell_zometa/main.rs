@@ -0,0 +1,297 @@
+/* JAM first attempt at a toy two pool model with HMM kinetics in
+ Rust.  This generalised model should be expanadable to any number of
+ pools and interactions.  I'll use rk4 integration algorithm only
+ because it is what I have used historically and it worked!  These
+ biological systems, comprised of Henri-Michaelis-Menten (HMM) kinetic
+ equations are usually not stiff.  This model follows the structure of
+the accompanying diagram called "Two Pool Model.pdf */
+
+
+// First produced by Pablo Zamora, Sieglord, and JAM at Norwich UK on
+// 2026_01_26
+
+//Last updated on 2026_02_01
+
+mod diagnostics;
+mod fit;
+mod integrator;
+mod model;
+
+use model::{Input, Model};
+use russell_lab::NumVector;
+use russell_ode::{OdeSolver, Params};
+use gnuplot::*;
+use std::thread::sleep;
+use std::time::Duration;
+
+// --- Non-State Variable Extraction ---
+
+/// This struct holds "non-state" variables (results like concentrations and
+/// fluxes) that are calculated during the ODE integration but are not part
+/// of the state vector. It is sized at runtime from the loaded `Model` so
+/// plotting and CSV output work for any topology, not just two pools.
+#[derive(Debug, Clone)]
+pub struct AuxiliaryResults {
+    // Concentrations, one per pool, in `model.pools` order.
+    pub concentrations: Vec<f64>,
+    // Fluxes, one per flux, in `model.fluxes` order.
+    pub fluxes: Vec<f64>,
+}
+
+impl AuxiliaryResults {
+    // Initializer for the auxiliary results.
+    pub(crate) fn new(model: &Model) -> Self {
+        AuxiliaryResults {
+            concentrations: model
+                .pools
+                .iter()
+                .map(|p| p.initial_amount / p.volume)
+                .collect(),
+            fluxes: vec![0.0; model.fluxes.len()],
+        }
+    }
+}
+
+// Runs `fit` mode: load a model and a table of observed pool amounts,
+// then fit each flux's Vmax/K to the data and print the result.
+fn run_fit_mode(model_path: &str, data_path: &str) {
+    let model = Model::load(model_path).expect("failed to load model file");
+    let data = fit::ObservedData::load(data_path).expect("failed to load observed-data file");
+
+    let result = fit::fit(model, &data, 200, 1e-3);
+
+    println!("Fitted flux parameters (Vmax, K):");
+    for (i, (vmax, k)) in result.fluxes.iter().enumerate() {
+        println!("  flux[{}]: Vmax = {:.6}, K = {:.6}", i, vmax, k);
+    }
+    println!("Final cost: {:.6}", result.cost);
+}
+
+// Runs `convergence` mode: solve the same model over a geometric
+// sequence of step sizes and report the empirical order of accuracy.
+fn run_convergence_mode(model_path: &str, method_name: &str, t_end: f64, dt: f64, levels: usize) {
+    let model = Model::load(model_path).expect("failed to load model file");
+    let method = integrator::parse_method(method_name).expect("unknown integration method");
+
+    let report = integrator::convergence_study(&model, method, t_end, dt, levels);
+
+    println!("Convergence study for {} (reference: dt = {:.6e}):", method_name, dt / 2f64.powi(levels as i32));
+    println!("  dt, error");
+    for row in &report.rows {
+        println!("  {:.6e}, {:.6e}", row.dt, row.error);
+    }
+    println!("Empirical order r = {:.3}", report.order);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // `fit <model.toml> <observed.csv>` runs the parameter-estimation
+    // mode instead of the live simulation/plotting loop below.
+    if args.get(1).map(String::as_str) == Some("fit") {
+        let model_path = args.get(2).map(String::as_str).unwrap_or("models/two_pool.toml");
+        let data_path = args.get(3).expect("fit mode requires an observed-data CSV file");
+        run_fit_mode(model_path, data_path);
+        return;
+    }
+
+    // `convergence <model.toml> <method> <t_end> <dt> <levels>` runs an
+    // order-of-accuracy study instead of the live simulation.
+    if args.get(1).map(String::as_str) == Some("convergence") {
+        let model_path = args.get(2).map(String::as_str).unwrap_or("models/two_pool.toml");
+        let method_name = args.get(3).map(String::as_str).unwrap_or("rk4");
+        let t_end: f64 = args.get(4).map(|s| s.parse().expect("t_end must be a number")).unwrap_or(10.0);
+        let dt: f64 = args.get(5).map(|s| s.parse().expect("dt must be a number")).unwrap_or(0.1);
+        let levels: usize = args.get(6).map(|s| s.parse().expect("levels must be an integer")).unwrap_or(5);
+        run_convergence_mode(model_path, method_name, t_end, dt, levels);
+        return;
+    }
+
+    // 0. Load the model description (pools + fluxes) from a TOML file.
+    // The path, and the integration method, can be overridden on the
+    // command line; they otherwise default to the original two-pool
+    // topology solved with Rk4.
+    let model_path = args.get(1).cloned().unwrap_or_else(|| "models/two_pool.toml".to_string());
+    let method_name = args.get(2).cloned().unwrap_or_else(|| "rk4".to_string());
+    let model = Model::load(&model_path).expect("failed to load model file");
+
+    let n_pools = model.n_states();
+    let inputs: Vec<Input> = model.pools.iter().map(|p| p.input.clone()).collect();
+    let flux_source: Vec<Option<usize>> = model
+        .fluxes
+        .iter()
+        .map(|f| f.source.as_deref().and_then(|n| model.pool_index(n)))
+        .collect();
+    let flux_target: Vec<Option<usize>> = model
+        .fluxes
+        .iter()
+        .map(|f| f.target.as_deref().and_then(|n| model.pool_index(n)))
+        .collect();
+
+    // 1. Define the system of differential equations. `inputs`/`flux_source`/
+    // `flux_target` above are kept around for the diagnostics below, which
+    // need the originals after every step; `Model::compile` builds its own
+    // copies for the closure.
+    let system = model.compile();
+
+    // 2. Configure the solver (method chosen via the CLI/config switch
+    // above; "rk4", "dopri5", "dopri8", or "radau5" for stiff systems).
+    let params = Params::new(integrator::parse_method(&method_name).expect("unknown integration method"));
+    let mut solver = OdeSolver::new(params, system).expect("Solver
+    initialization failed");
+
+      // 3. Set Initial Conditions
+    // start time
+    let mut t = 0.0;
+    // integration interval
+    let dt = 0.1;
+    // Initial metabolite amounts in pools
+    let initial: Vec<f64> = model.pools.iter().map(|p| p.initial_amount).collect();
+    let mut y = NumVector::from(&initial);
+
+    let mut results = AuxiliaryResults::new(&model);
+
+    // Stop points for the time-stepping loop: the regular dt grid, plus
+    // every input's switch times, so the integrator lands exactly on a
+    // discontinuity instead of stepping over it.
+    // 800 steps (t_end = 80) rather than 100: the default two-pool model
+    // doesn't bring ||dydt|| under the diagnostics' 1e-4 tolerance until
+    // roughly t ~= 42, so a shorter run never exercises the steady-state
+    // path end-to-end.
+    let num_steps = 800;
+    let t_end = num_steps as f64 * dt;
+    let stops = model.stop_points(t_end, dt);
+
+    let mut fg = Figure::new();
+    let mut state_trace = Vec::with_capacity(stops.len() + 1);
+    let mut results_trace = Vec::with_capacity(stops.len() + 1);
+    let mut t_trace = Vec::with_capacity(stops.len() + 1);
+    t_trace.push(t);
+    state_trace.push(y.clone());
+    results_trace.push(results.clone());
+
+    // Mass-balance and steady-state diagnostics, tracked alongside the
+    // raw pool traces: steady state is flagged once ||dydt|| stays below
+    // `tol` for 10 consecutive stop points.
+    let mut diag = diagnostics::Diagnostics::new(1e-4, 10);
+    let mut diag_trace: Vec<diagnostics::DiagnosticsRow> = Vec::with_capacity(stops.len() + 1);
+
+    // 4. Time-stepping Loop
+    let pool_header: Vec<String> = model.pools.iter().map(|p| p.name.clone()).collect();
+    let con_header: Vec<String> = model.pools.iter().map(|p| format!("Con{}", p.name)).collect();
+    let flux_header: Vec<String> = model.fluxes.iter().map(|f| format!("F{}", f.name)).collect();
+    println!(
+        "Time, {}, {}, {}, Balance, NegativeConc, SteadyState",
+        pool_header.join(", "),
+        con_header.join(", "),
+        flux_header.join(", ")
+    );
+    //Integrate from one stop point to the next, rather than blindly
+    //stepping by dt, so an input switch is never stepped over.
+    for &next_stop in &stops {
+        let step_dt = next_stop - t;
+        let total_prev: f64 = (0..n_pools).map(|i| y[i]).sum();
+
+        // Advance the simulation.
+        // The 'results' struct is passed mutably so it captures the values
+        // calculated inside the system function at the end of the step.
+        solver
+	//How would I know what to fill in the () for .solve?
+            .solve(&mut y, t, next_stop, None, &mut results)
+            .expect("Solver failed");
+        t = next_stop;
+
+        // Recover the derivative at the end of the step from the
+        // auxiliary results the solver just filled in, without calling
+        // back into the system closure.
+        let total_now: f64 = (0..n_pools).map(|i| y[i]).sum();
+        let mut dydt_now = vec![0.0; n_pools];
+        for i in 0..n_pools {
+            dydt_now[i] = inputs[i].value_at(t);
+        }
+        for fi in 0..flux_source.len() {
+            if let Some(src) = flux_source[fi] {
+                dydt_now[src] -= results.fluxes[fi];
+            }
+            if let Some(tgt) = flux_target[fi] {
+                dydt_now[tgt] += results.fluxes[fi];
+            }
+        }
+        let y_now: Vec<f64> = (0..n_pools).map(|i| y[i]).collect();
+        let diag_row = diag.step(
+            &model,
+            t,
+            &y_now,
+            &dydt_now,
+            &results,
+            &flux_target,
+            total_prev,
+            total_now,
+            step_dt,
+        );
+
+        t_trace.push(t);
+        state_trace.push(y.clone());
+        results_trace.push(results.clone());
+        diag_trace.push(diag_row);
+
+        // Plot the trace.
+        fg.clear_axes();
+        let pools_axes = fg
+            .axes2d()
+            .set_pos_grid(4, 1, 0)
+            .set_x_range(Fix(0.), Fix(t_end));
+        for (i, pool) in model.pools.iter().enumerate() {
+            pools_axes.lines_points(&t_trace, state_trace.iter().map(|y| y[i]), &[Caption(&pool.name)]);
+        }
+        let cons_axes = fg
+            .axes2d()
+            .set_pos_grid(4, 1, 1)
+            .set_x_range(Fix(0.), Fix(t_end));
+        for (i, pool) in model.pools.iter().enumerate() {
+            cons_axes.lines_points(
+                &t_trace,
+                results_trace.iter().map(|r| r.concentrations[i]),
+                &[Caption(&format!("Con {}", pool.name))],
+            );
+        }
+        let fluxes_axes = fg
+            .axes2d()
+            .set_pos_grid(4, 1, 2)
+            .set_x_range(Fix(0.), Fix(t_end));
+        for (fi, flux) in model.fluxes.iter().enumerate() {
+            fluxes_axes.lines_points(
+                &t_trace,
+                results_trace.iter().map(|r| r.fluxes[fi]),
+                &[Caption(&format!("Flux {}", flux.name))],
+            );
+        }
+        // Mass-balance diagnostics panel: should hover around 0.
+        fg.axes2d()
+            .set_pos_grid(4, 1, 3)
+            .set_x_range(Fix(0.), Fix(t_end))
+            .lines_points(&t_trace[1..], diag_trace.iter().map(|d| d.balance), &[Caption("Balance")]);
+        fg.show_and_keep_running().unwrap();
+        sleep(Duration::from_millis(50));
+
+        // Print the State variables (Pools) and Non-State variables (Cons/Fluxes)
+        let pools_csv: Vec<String> = (0..n_pools).map(|i| format!("{:.4}", y[i])).collect();
+        let cons_csv: Vec<String> = results.concentrations.iter().map(|v| format!("{:.4}", v)).collect();
+        let fluxes_csv: Vec<String> = results.fluxes.iter().map(|v| format!("{:.4}", v)).collect();
+        println!(
+            "{:.2}, {}, {}, {}, {:.6}, {}, {}",
+            t,
+            pools_csv.join(", "),
+            cons_csv.join(", "),
+            fluxes_csv.join(", "),
+            diag_trace.last().unwrap().balance,
+            diag_trace.last().unwrap().negative_concentration,
+            diag_trace.last().unwrap().steady_state
+        );
+    }
+
+    if let Some(steady_pools) = &diag.steady_state_pools {
+        println!("Steady state reached. Pool amounts: {:?}", steady_pools);
+        println!("Steady state fluxes: {:?}", diag.steady_state_fluxes.as_ref().unwrap());
+    }
+}
@@ -0,0 +1,375 @@
+// Model-description subsystem.
+//
+// Rather than hand-wiring a fixed topology (pools, volumes, fluxes) as
+// constants in `main`, the network is declared in a TOML model file as a
+// list of named pools and a list of directed Hill-kinetics fluxes
+// between them -- similar in spirit to how NMODL lets you write a
+// mechanism as a set of kinetic reaction schemes instead of raw code.
+
+use crate::AuxiliaryResults;
+use russell_ode::System;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// A single compartment ("pool") in the network: a volume, an initial
+/// amount of metabolite, and an external input, constant or time-varying.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Pool {
+    pub name: String,
+    pub volume: f64,
+    pub initial_amount: f64,
+    /// External input flowing into this pool (defaults to a constant 0).
+    #[serde(default)]
+    pub input: Input,
+}
+
+/// An external input into a pool: either a plain constant, or a
+/// piecewise-constant forcing term that switches on only during specified
+/// intervals (as in the Collins toggle-switch model).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Input {
+    Constant(f64),
+    Steps(Vec<InputStep>),
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::Constant(0.0)
+    }
+}
+
+/// One interval `[start, end)` during which an `Input::Steps` input is
+/// active at `value`; it is 0 outside every declared interval.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InputStep {
+    pub start: f64,
+    pub end: f64,
+    pub value: f64,
+}
+
+impl Input {
+    /// The active input value at time `t`.
+    pub fn value_at(&self, t: f64) -> f64 {
+        match self {
+            Input::Constant(v) => *v,
+            Input::Steps(steps) => steps
+                .iter()
+                .find(|s| t >= s.start && t < s.end)
+                .map(|s| s.value)
+                .unwrap_or(0.0),
+        }
+    }
+
+    /// The times at which this input switches on/off, so the integrator
+    /// can be made to stop exactly on them instead of stepping over a
+    /// discontinuity.
+    pub fn switch_times(&self) -> Vec<f64> {
+        match self {
+            Input::Constant(_) => Vec::new(),
+            Input::Steps(steps) => {
+                let mut times: Vec<f64> = steps.iter().flat_map(|s| [s.start, s.end]).collect();
+                times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                times.dedup();
+                times
+            }
+        }
+    }
+}
+
+/// A directed Hill-kinetics flux that drains `source` and feeds `target`.
+/// `target` may be omitted to model an outflow to outside the network
+/// (e.g. to waste). `source` cannot be meaningfully omitted: the flux is
+/// evaluated from the source pool's concentration, and a missing source
+/// is treated as concentration 0, which `hill_flux` maps to a permanent 0
+/// -- there is no external-inflow flux yet. Model an external inflow via
+/// the target pool's `input` instead. `n` is the cooperativity exponent;
+/// `n = 1` recovers plain Michaelis-Menten saturation.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Flux {
+    pub name: String,
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub vmax: f64,
+    pub k: f64,
+    #[serde(default = "default_hill_n")]
+    pub n: f64,
+}
+
+fn default_hill_n() -> f64 {
+    1.0
+}
+
+/// Evaluate a Hill-kinetics flux `Vmax * (con/K)^n / (1 + (con/K)^n)`.
+/// Reduces to the plain Michaelis-Menten form `Vmax / (1 + K/con)` when
+/// `n == 1`. Guards the `con <= 0` edge case (no substrate) to a clean 0
+/// flux instead of raising a non-positive value to a possibly fractional
+/// power.
+pub fn hill_flux(vmax: f64, k: f64, n: f64, con: f64) -> f64 {
+    if con <= 0.0 {
+        return 0.0;
+    }
+    let ratio_n = (con / k).powf(n);
+    vmax * ratio_n / (1.0 + ratio_n)
+}
+
+/// The parsed model: every pool and flux that makes up an arbitrary
+/// N-pool kinetic network.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Model {
+    pub pools: Vec<Pool>,
+    pub fluxes: Vec<Flux>,
+}
+
+impl Model {
+    /// Load and parse a model description from a TOML file, and validate
+    /// that every flux's `source`/`target` resolves to a declared pool.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read model file {}: {}", path.display(), e))?;
+        let model: Model = toml::from_str(&text)
+            .map_err(|e| format!("failed to parse model file {}: {}", path.display(), e))?;
+        model
+            .validate()
+            .map_err(|e| format!("invalid model file {}: {}", path.display(), e))?;
+        Ok(model)
+    }
+
+    /// Check that every non-`None` `Flux::source`/`target` names a pool
+    /// that actually exists. A name that fails to resolve (e.g. a typo)
+    /// would otherwise be silently treated the same as an omitted end --
+    /// a phantom inflow/outflow with no error.
+    pub fn validate(&self) -> Result<(), String> {
+        for flux in &self.fluxes {
+            if let Some(name) = &flux.source {
+                if self.pool_index(name).is_none() {
+                    return Err(format!("flux {:?}: source pool {:?} does not exist", flux.name, name));
+                }
+            }
+            if let Some(name) = &flux.target {
+                if self.pool_index(name).is_none() {
+                    return Err(format!("flux {:?}: target pool {:?} does not exist", flux.name, name));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of state variables, i.e. one per pool.
+    pub fn n_states(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Index of a pool by name, used to resolve `Flux::source`/`target`.
+    pub fn pool_index(&self, name: &str) -> Option<usize> {
+        self.pools.iter().position(|p| p.name == name)
+    }
+
+    /// Every time in `(0, t_end]` at which some pool's input switches
+    /// on/off, sorted and deduplicated.
+    pub(crate) fn input_switch_times(&self, t_end: f64) -> Vec<f64> {
+        let mut times: Vec<f64> = self
+            .pools
+            .iter()
+            .flat_map(|p| p.input.switch_times())
+            .filter(|&s| s > 0.0 && s <= t_end)
+            .collect();
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        times.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+        times
+    }
+
+    /// Stop points for a time-stepping loop from `0` to `t_end`: the
+    /// regular `dt` grid plus every input switch time, so the integrator
+    /// can be made to land exactly on a discontinuity instead of
+    /// stepping over it.
+    pub fn stop_points(&self, t_end: f64, dt: f64) -> Vec<f64> {
+        let num_steps = (t_end / dt).round() as usize;
+        let mut stops: Vec<f64> = (1..=num_steps).map(|i| i as f64 * dt).collect();
+        if stops.last().map_or(true, |&last| (last - t_end).abs() > 1e-9) {
+            stops.push(t_end);
+        }
+        stops.extend(self.input_switch_times(t_end));
+        stops.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        stops.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+        stops
+    }
+
+    /// Compile this model into a ready-to-solve `russell_ode::System`:
+    /// the concentration/Hill-flux/dydt bookkeeping shared by the live
+    /// simulation, the fit mode, and the convergence study, so a fix to
+    /// this logic (e.g. how a missing source/target is handled) only
+    /// has to be made once.
+    pub fn compile(&self) -> System<'static, AuxiliaryResults> {
+        let n_pools = self.n_states();
+        let volumes: Vec<f64> = self.pools.iter().map(|p| p.volume).collect();
+        let inputs: Vec<Input> = self.pools.iter().map(|p| p.input.clone()).collect();
+        let flux_vmax: Vec<f64> = self.fluxes.iter().map(|f| f.vmax).collect();
+        let flux_k: Vec<f64> = self.fluxes.iter().map(|f| f.k).collect();
+        let flux_n: Vec<f64> = self.fluxes.iter().map(|f| f.n).collect();
+        let flux_source: Vec<Option<usize>> = self
+            .fluxes
+            .iter()
+            .map(|f| f.source.as_deref().and_then(|n| self.pool_index(n)))
+            .collect();
+        let flux_target: Vec<Option<usize>> = self
+            .fluxes
+            .iter()
+            .map(|f| f.target.as_deref().and_then(|n| self.pool_index(n)))
+            .collect();
+
+        System::new(n_pools, move |dydt, t, y, results: &mut AuxiliaryResults| {
+            // --- Calculate Concentrations ---
+            for i in 0..n_pools {
+                results.concentrations[i] = y[i] / volumes[i];
+            }
+
+            // --- Calculate Fluxes (Hill-kinetics equations) ---
+            for fi in 0..flux_vmax.len() {
+                let con_src = match flux_source[fi] {
+                    Some(idx) => results.concentrations[idx],
+                    None => 0.0,
+                };
+                results.fluxes[fi] = hill_flux(flux_vmax[fi], flux_k[fi], flux_n[fi], con_src);
+            }
+
+            // --- Specify the ODEs: start from each pool's input (possibly
+            // time-varying), then accumulate every flux's inflow into its
+            // target and outflow from its source. ---
+            for i in 0..n_pools {
+                dydt[i] = inputs[i].value_at(t);
+            }
+            for fi in 0..flux_vmax.len() {
+                if let Some(src) = flux_source[fi] {
+                    dydt[src] -= results.fluxes[fi];
+                }
+                if let Some(tgt) = flux_target[fi] {
+                    dydt[tgt] += results.fluxes[fi];
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Test-only model fixtures shared across this crate's test modules, so
+/// `fit`/`integrator` tests don't each hand-roll their own near-identical
+/// two-pool-one-flux `Model` literal.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+
+    /// A two-pool model (A -> B) with a single Hill flux of the given
+    /// `vmax`/`k` (`n = 1`), no external inputs.
+    pub(crate) fn two_pool_one_flux_model(vmax: f64, k: f64) -> Model {
+        Model {
+            pools: vec![
+                Pool { name: "A".into(), volume: 1.0, initial_amount: 10.0, input: Input::Constant(0.0) },
+                Pool { name: "B".into(), volume: 1.0, initial_amount: 0.0, input: Input::Constant(0.0) },
+            ],
+            fluxes: vec![Flux {
+                name: "AB".into(),
+                source: Some("A".into()),
+                target: Some("B".into()),
+                vmax,
+                k,
+                n: 1.0,
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hill_flux_is_zero_with_no_substrate() {
+        assert_eq!(hill_flux(18.0, 0.32, 2.0, 0.0), 0.0);
+        assert_eq!(hill_flux(18.0, 0.32, 2.0, -1.0), 0.0);
+    }
+
+    #[test]
+    fn hill_flux_n_equals_one_matches_michaelis_menten() {
+        let (vmax, k, con) = (18.0, 0.32, 0.75);
+        let hill = hill_flux(vmax, k, 1.0, con);
+        let mm = vmax / (1.0 + k / con);
+        assert!((hill - mm).abs() < 1e-12, "hill = {}, mm = {}", hill, mm);
+    }
+
+    fn model_with_flux(source: Option<&str>, target: Option<&str>) -> Model {
+        Model {
+            pools: vec![Pool {
+                name: "A".into(),
+                volume: 1.0,
+                initial_amount: 1.0,
+                input: Input::Constant(0.0),
+            }],
+            fluxes: vec![Flux {
+                name: "F".into(),
+                source: source.map(str::to_string),
+                target: target.map(str::to_string),
+                vmax: 1.0,
+                k: 1.0,
+                n: 1.0,
+            }],
+        }
+    }
+
+    #[test]
+    fn validate_accepts_resolvable_names_and_omitted_ends() {
+        assert!(model_with_flux(Some("A"), None).validate().is_ok());
+        assert!(model_with_flux(Some("A"), Some("A")).validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_an_unresolvable_pool_name() {
+        let err = model_with_flux(Some("A"), Some("Bb")).validate().unwrap_err();
+        assert!(err.contains("Bb"), "error should name the bad pool: {}", err);
+    }
+
+    #[test]
+    fn constant_input_is_always_active() {
+        let input = Input::Constant(3.0);
+        assert_eq!(input.value_at(0.0), 3.0);
+        assert_eq!(input.value_at(100.0), 3.0);
+        assert!(input.switch_times().is_empty());
+    }
+
+    #[test]
+    fn steps_input_is_zero_outside_every_interval() {
+        let input = Input::Steps(vec![InputStep { start: 1.0, end: 2.0, value: 5.0 }]);
+        assert_eq!(input.value_at(0.5), 0.0);
+        assert_eq!(input.value_at(2.0), 0.0);
+        assert_eq!(input.value_at(3.0), 0.0);
+    }
+
+    #[test]
+    fn steps_input_interval_is_half_open() {
+        let input = Input::Steps(vec![InputStep { start: 1.0, end: 2.0, value: 5.0 }]);
+        assert_eq!(input.value_at(1.0), 5.0, "start is inclusive");
+        assert_eq!(input.value_at(1.5), 5.0);
+        assert_eq!(input.value_at(2.0), 0.0, "end is exclusive");
+    }
+
+    #[test]
+    fn steps_input_uses_the_first_matching_interval_on_overlap() {
+        let input = Input::Steps(vec![
+            InputStep { start: 0.0, end: 2.0, value: 1.0 },
+            InputStep { start: 1.0, end: 3.0, value: 2.0 },
+        ]);
+        assert_eq!(input.value_at(1.5), 1.0, "first declared interval wins on overlap");
+    }
+
+    #[test]
+    fn steps_input_switch_times_are_sorted_and_deduplicated() {
+        let input = Input::Steps(vec![
+            InputStep { start: 1.0, end: 2.0, value: 5.0 },
+            InputStep { start: 2.0, end: 3.0, value: 1.0 },
+        ]);
+        assert_eq!(input.switch_times(), vec![1.0, 2.0, 3.0]);
+    }
+}